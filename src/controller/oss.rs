@@ -1,25 +1,77 @@
 use std::env;
+use std::io::{Read, Write};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 
-use axum::extract::{BodyStream, Path};
-use axum::routing::{get, post};
+use axum::body::StreamBody;
+use axum::extract::{BodyStream, Multipart, Path, Query};
+use axum::routing::{delete, get, post};
 use axum::{Extension, Json, Router};
 use axum_core::response::{IntoResponse, Response};
 use bincode;
 use bytes::Bytes;
-use hyper::body;
-use hyper::body::Body;
-use hyper::http::header::HeaderName;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use futures_util::{stream, StreamExt};
+use hyper::http::header::{
+    HeaderName, ACCEPT_ENCODING, CACHE_CONTROL, CONTENT_ENCODING, ETAG, IF_NONE_MATCH, RANGE, VARY,
+};
 use hyper::http::StatusCode;
 use hyper::http::{HeaderMap, HeaderValue};
 use log::warn;
 use md5;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
+use thiserror::Error;
+use tokio::io::AsyncWriteExt;
 use tokio::sync::RwLock;
 use wickdb::file::FileStorage;
+use wickdb::Iterator as WickDbIterator;
 use wickdb::{BytewiseComparator, Options, ReadOptions, WickDB, WriteOptions, DB};
 
+// chunk size for a Range response body
+const RANGE_CHUNK_SIZE: usize = 64 * 1024;
+
+// stored content is immutable and content-addressed, so cache indefinitely
+const CACHE_CONTROL_VALUE: &str = "public, max-age=31536000, immutable";
+
+// key is the record's content-address, used verbatim as its strong ETag
+fn if_none_match_matches(header_value: &str, key: &str) -> bool {
+    if header_value.trim() == "*" {
+        return true;
+    }
+    header_value.split(',').any(|candidate| {
+        let candidate = candidate.trim().trim_start_matches("W/").trim_matches('"');
+        candidate == key
+    })
+}
+
+// bytes HeaderValue rejects: control chars other than tab, and DEL
+fn is_forbidden_header_byte(b: u8) -> bool {
+    (b < 0x20 && b != 0x09) || b == 0x7F
+}
+
+// percent-encodes bytes HeaderValue would reject instead of panicking on them
+fn header_value_lossy(value: &str) -> HeaderValue {
+    if let Ok(header_value) = HeaderValue::from_str(value) {
+        return header_value;
+    }
+
+    let mut sanitized = Vec::with_capacity(value.len());
+    for byte in value.bytes() {
+        if is_forbidden_header_byte(byte) {
+            sanitized.push(b'%');
+            sanitized.extend_from_slice(format!("{:02X}", byte).as_bytes());
+        } else {
+            sanitized.push(byte);
+        }
+    }
+
+    HeaderValue::from_bytes(&sanitized).unwrap_or_else(|_| HeaderValue::from_static(""))
+}
+
 pub type SharedState = Arc<RwLock<State>>;
 
 pub struct State {
@@ -31,42 +83,184 @@ struct OssRecordStore {
     origin_name: Option<String>,
     origin_type: Option<String>,
     content_data: Option<Vec<u8>>,
+    // absent on records written before compression support landed; treated
+    // the same as `Some("identity")` (see `deserialize_record_store` below)
+    content_encoding: Option<String>,
 }
 
 impl OssRecordStore {
-    fn new(_name: Option<String>, _type: Option<String>, _data: Option<Vec<u8>>) -> Self {
+    fn new(
+        _name: Option<String>,
+        _type: Option<String>,
+        _data: Option<Vec<u8>>,
+        _encoding: Option<String>,
+    ) -> Self {
         OssRecordStore {
             origin_name: _name,
             origin_type: _type,
             content_data: _data,
+            content_encoding: _encoding,
         }
     }
 }
 
+// shape of a stored record before content_encoding existed; kept so those
+// blobs stay readable
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct OssRecordStoreV1 {
+    origin_name: Option<String>,
+    origin_type: Option<String>,
+    content_data: Option<Vec<u8>>,
+}
+
+// bincode is positional, so #[serde(default)] doesn't help a new field read
+// old blobs; fall back to the 3-field shape when the 4-field one fails
+fn deserialize_record_store(bytes: &[u8]) -> Result<OssRecordStore, bincode::Error> {
+    if let Ok(record) = bincode::deserialize::<OssRecordStore>(bytes) {
+        return Ok(record);
+    }
+
+    let legacy: OssRecordStoreV1 = bincode::deserialize(bytes)?;
+    Ok(OssRecordStore {
+        origin_name: legacy.origin_name,
+        origin_type: legacy.origin_type,
+        content_data: legacy.content_data,
+        content_encoding: None,
+    })
+}
+
 pub fn oss_routes() -> Router {
     // new WickDB state
     let opt = Options::<BytewiseComparator>::default();
-    let state = Arc::new(RwLock::new(State {
-        db: Option::from(
-            WickDB::open_db(
-                opt,
-                env::var("OSS_STORE_DIR").unwrap_or(String::from("oss_store")),
-                FileStorage::default(),
-            )
-            .unwrap(),
-        ),
-    }));
+    let db = WickDB::open_db(
+        opt,
+        env::var("OSS_STORE_DIR").unwrap_or(String::from("oss_store")),
+        FileStorage::default(),
+    );
+    if let Err(err) = &db {
+        // the store stays closed; handlers report OssError::StoreOpenError
+        // per request instead of the process aborting at startup
+        warn!("Store open error: {}", err.to_string());
+    }
+    let state = Arc::new(RwLock::new(State { db: db.ok() }));
 
     Router::new()
         .route("/record/:key", get(get_record_by_key))
+        .route("/record/:key", delete(delete_record_by_key))
         .route("/record", post(store_record))
+        .route("/record/multipart", post(store_record_multipart))
+        .route("/records", get(list_records))
         .layer(Extension(state))
 }
 
+/// Default and maximum page size for `GET /records`.
+const RECORDS_LIST_DEFAULT_LIMIT: usize = 100;
+const RECORDS_LIST_MAX_LIMIT: usize = 1000;
+
+#[derive(Debug, Deserialize)]
+struct ListRecordsQuery {
+    limit: Option<usize>,
+    cursor: Option<String>,
+}
+
+// Ok(None): no range, or not one we understand, serve the full response.
+// Err(()): a bytes= range that isn't satisfiable against total_len.
+fn parse_range(range_header: &str, total_len: u64) -> Result<Option<(u64, u64)>, ()> {
+    let prefix = "bytes=";
+    if !range_header.starts_with(prefix) {
+        return Ok(None);
+    }
+    let spec = &range_header[prefix.len()..];
+    // multiple ranges are not supported, fall back to a full response
+    if spec.contains(',') {
+        return Ok(None);
+    }
+
+    let mut parts = spec.splitn(2, '-');
+    let start_str = parts.next().unwrap_or("");
+    let end_str = parts.next().unwrap_or("");
+
+    if start_str.is_empty() {
+        // suffix range: bytes=-N, the last N bytes of the record
+        let suffix_len: u64 = match end_str.parse() {
+            Ok(v) => v,
+            Err(_) => return Ok(None),
+        };
+        if suffix_len == 0 || total_len == 0 {
+            return Err(());
+        }
+        let start = total_len.saturating_sub(suffix_len);
+        return Ok(Some((start, total_len - 1)));
+    }
+
+    let start: u64 = match start_str.parse() {
+        Ok(v) => v,
+        Err(_) => return Ok(None),
+    };
+    let end: u64 = if end_str.is_empty() {
+        total_len.saturating_sub(1)
+    } else {
+        match end_str.parse() {
+            Ok(v) => v,
+            Err(_) => return Ok(None),
+        }
+    };
+
+    if start > end || start >= total_len {
+        return Err(());
+    }
+
+    Ok(Some((start, end.min(total_len.saturating_sub(1)))))
+}
+
+// checks Accept-Encoding for codec, honoring a q=0 weight as "not acceptable"
+fn client_accepts_encoding(req_headers: &HeaderMap, codec: &str) -> bool {
+    let header_value = match req_headers.get(ACCEPT_ENCODING).and_then(|v| v.to_str().ok()) {
+        Some(v) => v,
+        None => return false,
+    };
+
+    let weight_of = |name_to_match: &str| {
+        header_value.split(',').find_map(|part| {
+            let mut segments = part.split(';');
+            let name = segments.next().unwrap_or("").trim();
+            if !name.eq_ignore_ascii_case(name_to_match) {
+                return None;
+            }
+            Some(
+                segments
+                    .find_map(|seg| seg.trim().strip_prefix("q="))
+                    .and_then(|q| q.parse::<f32>().ok())
+                    .unwrap_or(1.0),
+            )
+        })
+    };
+
+    // an explicit "codec;q=0" is a hard rejection even when a "*;q>0" is
+    // also present, so it has to be checked before falling back to "*"
+    if weight_of(codec) == Some(0.0) {
+        return false;
+    }
+
+    weight_of(codec).or_else(|| weight_of("*")).unwrap_or(0.0) > 0.0
+}
+
+// yields data in RANGE_CHUNK_SIZE pieces instead of one large allocation
+fn chunked_body(
+    data: Vec<u8>,
+) -> StreamBody<impl futures_util::Stream<Item = Result<Bytes, std::io::Error>>> {
+    let chunks: Vec<Result<Bytes, std::io::Error>> = data
+        .chunks(RANGE_CHUNK_SIZE)
+        .map(|chunk| Ok(Bytes::copy_from_slice(chunk)))
+        .collect();
+    StreamBody::new(stream::iter(chunks))
+}
+
 async fn get_record_by_key(
     Path(key): Path<String>,
+    req_headers: HeaderMap,
     Extension(state): Extension<SharedState>,
-) -> Result<(HeaderMap, Bytes), OssError> {
+) -> Result<Response, OssError> {
     let mut headers = HeaderMap::new();
     let rec_bin_md5 = hex::decode(&key.as_str());
 
@@ -76,13 +270,9 @@ async fn get_record_by_key(
         return Err(OssError::InvalidRecordKey);
     }
 
-    let rec_bin_vec = state
-        .read()
-        .await
-        .db
-        .as_ref()
-        .unwrap()
-        .get(ReadOptions::default(), &rec_bin_md5.unwrap().as_slice());
+    let guard = state.read().await;
+    let db = guard.db.as_ref().ok_or(OssError::StoreOpenError)?;
+    let rec_bin_vec = db.get(ReadOptions::default(), &rec_bin_md5.unwrap().as_slice());
 
     if rec_bin_vec.is_err() {
         // get error
@@ -94,31 +284,94 @@ async fn get_record_by_key(
         return Err(OssError::StoreGetNotFound);
     }
 
-    let record_store: OssRecordStore =
-        bincode::deserialize(&rec_bin_vec.unwrap().unwrap().as_slice()).unwrap();
+    // Existence is confirmed above, so a stale `If-None-Match` for a key that
+    // was since deleted (or never stored) gets the not-found error above
+    // instead of short-circuiting here.
+    if let Some(if_none_match) = req_headers.get(IF_NONE_MATCH) {
+        if if_none_match_matches(if_none_match.to_str().unwrap_or(""), key.as_str()) {
+            drop(guard);
+            let mut resp = StatusCode::NOT_MODIFIED.into_response();
+            resp.headers_mut()
+                .insert(ETAG, HeaderValue::from_str(&format!("\"{}\"", key)).unwrap());
+            resp.headers_mut()
+                .insert(CACHE_CONTROL, HeaderValue::from_static(CACHE_CONTROL_VALUE));
+            return Ok(resp);
+        }
+    }
+
+    let record_store =
+        deserialize_record_store(&rec_bin_vec.unwrap().unwrap().as_slice()).map_err(|err| {
+            warn!("Deserialize error [record key]: {}", err.to_string());
+            OssError::RecordDeserializeError
+        })?;
+    drop(guard);
 
     headers.insert(
         HeaderName::from_static("record-origin-name"),
-        HeaderValue::from_str(
-            &record_store
-                .origin_name
-                .unwrap_or(String::default())
-                .as_str(),
-        )
-        .unwrap(),
+        header_value_lossy(&record_store.origin_name.unwrap_or_default()),
     );
     headers.insert(
         HeaderName::from_static("record-origin-type"),
-        HeaderValue::from_str(
-            &record_store
-                .origin_type
-                .unwrap_or(String::default())
-                .as_str(),
-        )
-        .unwrap(),
+        header_value_lossy(&record_store.origin_type.unwrap_or_default()),
     );
+    headers.insert(
+        HeaderName::from_static("accept-ranges"),
+        HeaderValue::from_static("bytes"),
+    );
+    headers.insert(ETAG, HeaderValue::from_str(&format!("\"{}\"", key)).unwrap());
+    headers.insert(CACHE_CONTROL, HeaderValue::from_static(CACHE_CONTROL_VALUE));
+
+    let stored_data = record_store.content_data.unwrap_or_default();
+    let stored_encoding = record_store.content_encoding.as_deref();
+
+    if stored_encoding == Some(GZIP_ENCODING) {
+        // the response body for this key depends on Accept-Encoding, so a
+        // shared cache must not serve one representation for both
+        headers.insert(VARY, HeaderValue::from_static("accept-encoding"));
+    }
+
+    // serve the stored bytes as-is when the client accepts that encoding
+    // (zero-cost passthrough); otherwise decompress before responding
+    let content_data = match stored_encoding {
+        Some(GZIP_ENCODING) if client_accepts_encoding(&req_headers, GZIP_ENCODING) => {
+            headers.insert(CONTENT_ENCODING, HeaderValue::from_static(GZIP_ENCODING));
+            stored_data
+        }
+        Some(codec) => decode_content(stored_data, Some(codec))?,
+        None => stored_data,
+    };
+    let total_len = content_data.len() as u64;
 
-    return Ok((headers, Bytes::from(record_store.content_data.unwrap())));
+    if let Some(range_value) = req_headers.get(RANGE) {
+        let range_str = range_value.to_str().unwrap_or("");
+        match parse_range(range_str, total_len) {
+            Err(()) => {
+                warn!("Range not satisfiable [record key]");
+                return Err(OssError::RangeNotSatisfiable(total_len));
+            }
+            Ok(Some((start, end))) => {
+                headers.insert(
+                    HeaderName::from_static("content-range"),
+                    HeaderValue::from_str(&format!("bytes {}-{}/{}", start, end, total_len))
+                        .unwrap(),
+                );
+                let slice = content_data[start as usize..=end as usize].to_vec();
+                return Ok(
+                    (StatusCode::PARTIAL_CONTENT, headers, chunked_body(slice)).into_response()
+                );
+            }
+            Ok(None) => {
+                // not a range we understand, fall through to a full response
+            }
+        }
+    }
+
+    headers.insert(
+        HeaderName::from_static("content-length"),
+        HeaderValue::from_str(&total_len.to_string()).unwrap(),
+    );
+
+    Ok((StatusCode::OK, headers, Bytes::from(content_data)).into_response())
 }
 
 async fn store_record(
@@ -144,7 +397,7 @@ async fn store_record(
         return Err(OssError::HttpHeaderNotFound);
     }
 
-    if rec_content_length > 100 * 1024 * 1024 {
+    if rec_content_length > MAX_RECORD_SIZE {
         // size too big
         warn!("Invalid stored record [size is too big]");
         return Err(OssError::RecordTooBig);
@@ -157,49 +410,195 @@ async fn store_record(
     let mut rec_origin_name: Option<String> = None;
     let mut rec_origin_type: Option<String> = None;
 
-    let origin_name = headers.get(HeaderName::from_static("record-origin-name"));
-    if origin_name.is_some() {
-        rec_origin_name = Some(String::from(origin_name.unwrap().to_str().unwrap()));
+    if let Some(origin_name) = headers.get(HeaderName::from_static("record-origin-name")) {
+        let origin_name_str = origin_name.to_str().map_err(|err| {
+            warn!("Invalid header encoding [record-origin-name]: {}", err.to_string());
+            OssError::HeaderEncodingError
+        })?;
+        rec_origin_name = Some(String::from(origin_name_str));
     }
 
-    let origin_type = headers.get(HeaderName::from_static("record-origin-type"));
-    if origin_type.is_some() {
-        rec_origin_type = Some(String::from(origin_type.unwrap().to_str().unwrap()));
+    if let Some(origin_type) = headers.get(HeaderName::from_static("record-origin-type")) {
+        let origin_type_str = origin_type.to_str().map_err(|err| {
+            warn!("Invalid header encoding [record-origin-type]: {}", err.to_string());
+            OssError::HeaderEncodingError
+        })?;
+        rec_origin_type = Some(String::from(origin_type_str));
     }
 
-    let mut bytes_resp = vec![];
-    match body::to_bytes(Body::wrap_stream(stream)).await {
-        Ok(v) => {
-            bytes_resp.extend_from_slice(v.to_vec().as_slice());
-        }
+    let spill_path = spill_file_path();
+    let spill_result = spill_body_to_file(stream, &spill_path).await;
+
+    let raw_md5 = match spill_result {
+        Ok(digest) => digest,
         Err(err) => {
-            // read body stream error
+            let _ = tokio::fs::remove_file(&spill_path).await;
+            return Err(err);
+        }
+    };
+
+    // The spilled content is already content-addressed by its incremental
+    // hash, so a duplicate upload can be answered from the existence check
+    // alone, without ever reading the spill file back into memory.
+    if record_exists(&state, raw_md5.as_ref()).await? {
+        let _ = tokio::fs::remove_file(&spill_path).await;
+        return Ok(Bytes::from(
+            Json(json!({
+                "result": format!("{:x}", raw_md5),
+            }))
+            .to_string(),
+        ));
+    }
+
+    let bytes_resp = tokio::fs::read(&spill_path).await.map_err(|err| {
+        warn!("Read spill file error: {}", err.to_string());
+        OssError::HttpBodyReadError
+    });
+    let _ = tokio::fs::remove_file(&spill_path).await;
+    let bytes_resp = bytes_resp?;
+
+    let rec_key =
+        store_content_addressed(&state, rec_origin_name, rec_origin_type, bytes_resp, raw_md5)
+            .await?;
+
+    return Ok(Bytes::from(
+        Json(json!({
+            "result": rec_key,
+        }))
+        .to_string(),
+    ));
+}
+
+// max accepted size of a stored record's raw content
+const MAX_RECORD_SIZE: usize = 100 * 1024 * 1024;
+
+static SPILL_FILE_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+// unique path, under OSS_STORE_DIR, for an upload's temporary spill file
+fn spill_file_path() -> PathBuf {
+    let store_dir = env::var("OSS_STORE_DIR").unwrap_or(String::from("oss_store"));
+    let unique = SPILL_FILE_COUNTER.fetch_add(1, Ordering::Relaxed);
+    PathBuf::from(store_dir).join(format!(".spill-{}-{}", std::process::id(), unique))
+}
+
+// streams into path instead of buffering the upload in memory first, and
+// hashes each chunk as it arrives so there's no second full-size pass later
+async fn spill_body_to_file(
+    mut stream: BodyStream,
+    path: &PathBuf,
+) -> Result<md5::Digest, OssError> {
+    let mut file = tokio::fs::File::create(path).await.map_err(|err| {
+        warn!("Create spill file error: {}", err.to_string());
+        OssError::HttpBodyReadError
+    })?;
+
+    let mut hasher = md5::Context::new();
+    let mut total = 0usize;
+    while let Some(chunk) = stream.next().await {
+        let chunk = chunk.map_err(|err| {
             warn!("Read body stream error: {}", err.to_string());
-            return Err(OssError::HttpBodyReadError);
+            OssError::HttpBodyReadError
+        })?;
+
+        total += chunk.len();
+        if total > MAX_RECORD_SIZE {
+            warn!("Invalid stored record [size is too big]");
+            return Err(OssError::RecordTooBig);
         }
+
+        hasher.consume(&chunk);
+        file.write_all(&chunk).await.map_err(|err| {
+            warn!("Write spill file error: {}", err.to_string());
+            OssError::HttpBodyReadError
+        })?;
+    }
+
+    if total == 0 {
+        warn!("Invalid stored record [size is too small]");
+        return Err(OssError::RecordTooSmall);
     }
 
-    let rec_store = OssRecordStore::new(rec_origin_name, rec_origin_type, Some(bytes_resp));
-    let rec_bin_vec = bincode::serialize(&rec_store).unwrap();
-    let rec_bin_md5 = md5::compute(&rec_bin_vec.as_slice());
+    Ok(hasher.compute())
+}
 
-    let res_get = state
-        .read()
-        .await
-        .db
-        .as_ref()
-        .unwrap()
-        .get(ReadOptions::default(), &rec_bin_md5.as_ref());
+// a missing or unrecognized OSS_COMPRESSION_CODEC leaves content identity
+const GZIP_ENCODING: &str = "gzip";
 
-    if res_get.is_err() {
-        // get error
-        warn!("Get error");
-        return Err(OssError::StoreGetError);
-    } else if res_get.unwrap().is_none() {
-        // not found in store
-        let res_put = state.write().await.db.as_ref().unwrap().put(
+fn store_compression_codec() -> Option<&'static str> {
+    match env::var("OSS_COMPRESSION_CODEC") {
+        Ok(codec) if codec.eq_ignore_ascii_case(GZIP_ENCODING) => Some(GZIP_ENCODING),
+        _ => None,
+    }
+}
+
+fn gzip_compress(data: &[u8]) -> std::io::Result<Vec<u8>> {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(data)?;
+    encoder.finish()
+}
+
+fn gzip_decompress(data: &[u8]) -> std::io::Result<Vec<u8>> {
+    let mut decoder = GzDecoder::new(data);
+    let mut out = Vec::new();
+    decoder.read_to_end(&mut out)?;
+    Ok(out)
+}
+
+// None/"identity" content_encoding means data is already decompressed
+fn decode_content(data: Vec<u8>, content_encoding: Option<&str>) -> Result<Vec<u8>, OssError> {
+    match content_encoding {
+        Some(GZIP_ENCODING) => gzip_decompress(&data).map_err(|err| {
+            warn!("Decompress error [record key]: {}", err.to_string());
+            OssError::DecompressionError
+        }),
+        _ => Ok(data),
+    }
+}
+
+// lets a duplicate upload be recognized without reading it back into memory
+async fn record_exists(state: &SharedState, key_bytes: &[u8]) -> Result<bool, OssError> {
+    let guard = state.read().await;
+    let db = guard.db.as_ref().ok_or(OssError::StoreOpenError)?;
+    db.get(ReadOptions::default(), key_bytes)
+        .map(|v| v.is_some())
+        .map_err(|err| {
+            warn!("Get error: {}", err.to_string());
+            OssError::StoreGetError
+        })
+}
+
+// raw_md5 is the MD5 of content_data itself, computed by the caller
+// (incrementally when possible), so identical content dedups regardless of
+// how it's wrapped or compressed, and a caller that already hashed the
+// upload while streaming it never has to hash it again here
+async fn store_content_addressed(
+    state: &SharedState,
+    origin_name: Option<String>,
+    origin_type: Option<String>,
+    content_data: Vec<u8>,
+    raw_md5: md5::Digest,
+) -> Result<String, OssError> {
+    if !record_exists(state, raw_md5.as_ref()).await? {
+        let (content_data, content_encoding) = match store_compression_codec() {
+            Some(GZIP_ENCODING) => {
+                let compressed = gzip_compress(&content_data).map_err(|err| {
+                    warn!("Compress error: {}", err.to_string());
+                    OssError::CompressionError
+                })?;
+                (compressed, Some(String::from(GZIP_ENCODING)))
+            }
+            _ => (content_data, None),
+        };
+
+        let rec_store =
+            OssRecordStore::new(origin_name, origin_type, Some(content_data), content_encoding);
+        let rec_bin_vec = bincode::serialize(&rec_store).unwrap();
+
+        let guard = state.write().await;
+        let db = guard.db.as_ref().ok_or(OssError::StoreOpenError)?;
+        let res_put = db.put(
             WriteOptions::default(),
-            &rec_bin_md5.as_ref(),
+            &raw_md5.as_ref(),
             &rec_bin_vec.as_ref(),
         );
 
@@ -210,56 +609,253 @@ async fn store_record(
         }
     }
 
-    return Ok(Bytes::from(
+    Ok(format!("{:x}", raw_md5))
+}
+
+// for ingestion paths that already hold the whole upload in memory (e.g. one
+// multipart field) and have no streamed digest to reuse
+async fn dedup_store_record(
+    state: &SharedState,
+    origin_name: Option<String>,
+    origin_type: Option<String>,
+    content_data: Vec<u8>,
+) -> Result<String, OssError> {
+    let raw_md5 = md5::compute(&content_data);
+    store_content_addressed(state, origin_name, origin_type, content_data, raw_md5).await
+}
+
+/// Lists stored records by walking the underlying `wickdb` keyspace in key
+/// order, starting just after `cursor` (an opaque hex-encoded last-key
+/// token) when one is given. Keys are content hashes, so this is a plain
+/// scan rather than a lookup by name.
+async fn list_records(
+    Query(query): Query<ListRecordsQuery>,
+    Extension(state): Extension<SharedState>,
+) -> Result<Bytes, OssError> {
+    let limit = query
+        .limit
+        .unwrap_or(RECORDS_LIST_DEFAULT_LIMIT)
+        .min(RECORDS_LIST_MAX_LIMIT)
+        .max(1);
+
+    let guard = state.read().await;
+    let db = guard.db.as_ref().ok_or(OssError::StoreOpenError)?;
+
+    let mut iter = db.new_iter(ReadOptions::default()).map_err(|err| {
+        warn!("Iterate error [records]: {}", err.to_string());
+        OssError::StoreListError
+    })?;
+
+    match &query.cursor {
+        Some(cursor) => {
+            let cursor_bin = hex::decode(cursor.as_str()).map_err(|_| {
+                warn!("Invalid hex string [cursor]");
+                OssError::InvalidRecordKey
+            })?;
+            iter.seek(&cursor_bin);
+            if iter.valid() && iter.key() == cursor_bin.as_slice() {
+                iter.next();
+            }
+        }
+        None => iter.seek_to_first(),
+    }
+
+    let mut records = vec![];
+    let mut next_cursor = None;
+    while iter.valid() && records.len() < limit {
+        let key = iter.key().to_vec();
+        if let Ok(record_store) = deserialize_record_store(iter.value()) {
+            // report the original size, not the on-disk (possibly
+            // compressed) one, so it matches what GET actually returns
+            let size = match record_store.content_encoding.as_deref() {
+                Some(GZIP_ENCODING) => record_store
+                    .content_data
+                    .as_deref()
+                    .and_then(|data| gzip_decompress(data).ok())
+                    .map(|data| data.len())
+                    .unwrap_or(0),
+                _ => record_store.content_data.map(|d| d.len()).unwrap_or(0),
+            };
+            records.push(json!({
+                "key": hex::encode(&key),
+                "origin_name": record_store.origin_name,
+                "origin_type": record_store.origin_type,
+                "size": size,
+            }));
+        }
+        next_cursor = Some(hex::encode(&key));
+        iter.next();
+    }
+
+    if !iter.valid() {
+        next_cursor = None;
+    }
+
+    Ok(Bytes::from(
         Json(json!({
-            "result": format!("{:x}", rec_bin_md5).to_string(),
+            "records": records,
+            "cursor": next_cursor,
         }))
         .to_string(),
-    ));
+    ))
+}
+
+/// Deletes the single dedup'd blob addressed by `key`. Because keys are
+/// content hashes, this removes the record itself rather than "a copy" of
+/// it; any other record that happened to store identical bytes would share
+/// the same key and be removed along with it.
+async fn delete_record_by_key(
+    Path(key): Path<String>,
+    Extension(state): Extension<SharedState>,
+) -> Result<Bytes, OssError> {
+    let rec_bin_md5 = hex::decode(&key.as_str());
+
+    if rec_bin_md5.is_err() {
+        // invalid hex string
+        warn!("Invalid hex string [record key]");
+        return Err(OssError::InvalidRecordKey);
+    }
+
+    let guard = state.write().await;
+    let db = guard.db.as_ref().ok_or(OssError::StoreOpenError)?;
+    let res_delete = db.delete(WriteOptions::default(), &rec_bin_md5.unwrap().as_slice());
+
+    if res_delete.is_err() {
+        // delete error
+        warn!("Delete error [record key]");
+        return Err(OssError::StoreDeleteError);
+    }
+
+    Ok(Bytes::from(
+        Json(json!({
+            "result": key,
+        }))
+        .to_string(),
+    ))
+}
+
+async fn store_record_multipart(
+    mut multipart: Multipart,
+    Extension(state): Extension<SharedState>,
+) -> Result<Bytes, OssError> {
+    // Keyed by field position rather than filename/field name: multipart
+    // requests routinely carry several parts with the same (or no) filename,
+    // e.g. an `<input multiple>` field, and those would otherwise collide in
+    // a name-keyed map and silently drop all but the last result.
+    let mut results = vec![];
+    let mut field_index = 0usize;
+
+    while let Some(field) = multipart.next_field().await.map_err(|err| {
+        warn!("Read multipart field error: {}", err.to_string());
+        OssError::HttpBodyReadError
+    })? {
+        let field_name = field.name().map(String::from);
+        let origin_name = field.file_name().map(String::from);
+        let origin_type = field.content_type().map(String::from);
+
+        let data = field.bytes().await.map_err(|err| {
+            warn!("Read multipart field bytes error: {}", err.to_string());
+            OssError::HttpBodyReadError
+        })?;
+
+        if data.len() > MAX_RECORD_SIZE {
+            // size too big
+            warn!("Invalid stored record [size is too big]");
+            return Err(OssError::RecordTooBig);
+        } else if data.is_empty() {
+            // size too small
+            warn!("Invalid stored record [size is too small]");
+            return Err(OssError::RecordTooSmall);
+        }
+
+        let rec_key =
+            dedup_store_record(&state, origin_name.clone(), origin_type, data.to_vec()).await?;
+
+        results.push(json!({
+            "index": field_index,
+            "field": field_name,
+            "origin_name": origin_name,
+            "key": rec_key,
+        }));
+        field_index += 1;
+    }
+
+    Ok(Bytes::from(
+        Json(json!({ "results": results })).to_string(),
+    ))
 }
 
 impl IntoResponse for OssError {
     fn into_response(self) -> Response {
-        let (status, error_message) = match self {
-            OssError::InvalidRecordKey => {
-                (StatusCode::BAD_REQUEST, "Invalid hex string [record key]")
-            }
-            OssError::StoreGetError => {
-                (StatusCode::INTERNAL_SERVER_ERROR, "Get error [record key]")
-            }
-            OssError::StorePutError => (StatusCode::INTERNAL_SERVER_ERROR, "Put error [record"),
-            OssError::StoreGetNotFound => (StatusCode::BAD_REQUEST, "Not found [record key]"),
-            OssError::HttpHeaderNotFound => (
-                StatusCode::BAD_REQUEST,
-                "Not found valid header [content-length]",
-            ),
-            OssError::RecordTooBig => (
-                StatusCode::BAD_REQUEST,
-                "Invalid stored record [size is too big]",
-            ),
-            OssError::RecordTooSmall => (
-                StatusCode::BAD_REQUEST,
-                "Invalid stored record [size is too small]",
-            ),
-            OssError::HttpBodyReadError => {
-                (StatusCode::INTERNAL_SERVER_ERROR, "Read body stream error")
-            }
+        let status = match &self {
+            OssError::InvalidRecordKey => StatusCode::BAD_REQUEST,
+            OssError::StoreGetError => StatusCode::INTERNAL_SERVER_ERROR,
+            OssError::StorePutError => StatusCode::INTERNAL_SERVER_ERROR,
+            OssError::StoreGetNotFound => StatusCode::BAD_REQUEST,
+            OssError::HttpHeaderNotFound => StatusCode::BAD_REQUEST,
+            OssError::RecordTooBig => StatusCode::BAD_REQUEST,
+            OssError::RecordTooSmall => StatusCode::BAD_REQUEST,
+            OssError::HttpBodyReadError => StatusCode::INTERNAL_SERVER_ERROR,
+            OssError::RangeNotSatisfiable(_) => StatusCode::RANGE_NOT_SATISFIABLE,
+            OssError::StoreDeleteError => StatusCode::INTERNAL_SERVER_ERROR,
+            OssError::StoreListError => StatusCode::INTERNAL_SERVER_ERROR,
+            OssError::RecordDeserializeError => StatusCode::INTERNAL_SERVER_ERROR,
+            OssError::HeaderEncodingError => StatusCode::BAD_REQUEST,
+            OssError::StoreOpenError => StatusCode::INTERNAL_SERVER_ERROR,
+            OssError::CompressionError => StatusCode::INTERNAL_SERVER_ERROR,
+            OssError::DecompressionError => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        let content_range = match &self {
+            OssError::RangeNotSatisfiable(total_len) => Some(format!("bytes */{}", total_len)),
+            _ => None,
         };
+
         let body = Json(json!({
-            "error": error_message,
+            "error": self.to_string(),
         }));
-        (status, body).into_response()
+        let mut resp = (status, body).into_response();
+        if let Some(content_range) = content_range {
+            resp.headers_mut().insert(
+                HeaderName::from_static("content-range"),
+                HeaderValue::from_str(&content_range).unwrap(),
+            );
+        }
+        resp
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Error)]
 enum OssError {
+    #[error("Invalid hex string [record key]")]
     InvalidRecordKey,
+    #[error("Get error [record key]")]
     StoreGetError,
+    #[error("Put error [record key]")]
     StorePutError,
+    #[error("Not found [record key]")]
     StoreGetNotFound,
+    #[error("Not found valid header [content-length]")]
     HttpHeaderNotFound,
+    #[error("Invalid stored record [size is too big]")]
     RecordTooBig,
+    #[error("Invalid stored record [size is too small]")]
     RecordTooSmall,
+    #[error("Read body stream error")]
     HttpBodyReadError,
+    #[error("Range not satisfiable [record key]")]
+    RangeNotSatisfiable(u64),
+    #[error("Delete error [record key]")]
+    StoreDeleteError,
+    #[error("Iterate error [records]")]
+    StoreListError,
+    #[error("Failed to deserialize stored record")]
+    RecordDeserializeError,
+    #[error("Invalid header encoding")]
+    HeaderEncodingError,
+    #[error("Store is not available")]
+    StoreOpenError,
+    #[error("Compress error")]
+    CompressionError,
+    #[error("Decompress error [record key]")]
+    DecompressionError,
 }
@@ -1,7 +1,10 @@
+use axum_server::tls_rustls::RustlsConfig;
 use dotenv::dotenv;
 use flexi_logger::{detailed_format, Duplicate};
 use log::info;
+use std::env;
 use std::net::SocketAddr;
+use std::time::Duration;
 use tokio::signal;
 
 use crate::router::register_router;
@@ -66,6 +69,14 @@ async fn shutdown_signal() {
     }
 }
 
+/// Waits for `shutdown_signal` and then asks an `axum_server::Handle` to
+/// drain in-flight TLS connections, mirroring `with_graceful_shutdown` on
+/// the plain-HTTP path.
+async fn graceful_shutdown_tls(handle: axum_server::Handle) {
+    shutdown_signal().await;
+    handle.graceful_shutdown(Some(Duration::from_secs(10)));
+}
+
 #[tokio::main]
 async fn main() {
     // init log
@@ -74,15 +85,35 @@ async fn main() {
     dotenv().ok();
 
     // run it
-    let listen_addr_str = "0.0.0.0:3000";
+    let listen_addr_str =
+        env::var("OSS_LISTEN_ADDR").unwrap_or_else(|_| String::from("0.0.0.0:3000"));
     let listen_addr: SocketAddr = listen_addr_str.parse().unwrap();
 
     let router = register_router();
 
-    info!("listening on {}", listen_addr);
-    axum::Server::bind(&listen_addr)
-        .serve(router.into_make_service())
-        .with_graceful_shutdown(shutdown_signal())
-        .await
-        .unwrap();
+    let tls_cert = env::var("OSS_TLS_CERT").ok();
+    let tls_key = env::var("OSS_TLS_KEY").ok();
+
+    if let (Some(cert_path), Some(key_path)) = (tls_cert, tls_key) {
+        info!("listening on {} (tls)", listen_addr);
+        let tls_config = RustlsConfig::from_pem_file(cert_path, key_path)
+            .await
+            .unwrap_or_else(|e| panic!("failed to load TLS cert/key, err: {}", e));
+
+        let handle = axum_server::Handle::new();
+        tokio::spawn(graceful_shutdown_tls(handle.clone()));
+
+        axum_server::bind_rustls(listen_addr, tls_config)
+            .handle(handle)
+            .serve(router.into_make_service())
+            .await
+            .unwrap();
+    } else {
+        info!("listening on {}", listen_addr);
+        axum::Server::bind(&listen_addr)
+            .serve(router.into_make_service())
+            .with_graceful_shutdown(shutdown_signal())
+            .await
+            .unwrap();
+    }
 }